@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+
+const BOOTSTRAP_SERVERS_ENV: &str = "FX_PLOT_KAFKA_BOOTSTRAP_SERVERS";
+const GROUP_ID_ENV: &str = "FX_PLOT_KAFKA_GROUP_ID";
+const AUTO_OFFSET_RESET_ENV: &str = "FX_PLOT_KAFKA_AUTO_OFFSET_RESET";
+const SOCKET_TIMEOUT_MS_ENV: &str = "FX_PLOT_KAFKA_SOCKET_TIMEOUT_MS";
+const TOPIC_ENV: &str = "FX_PLOT_KAFKA_TOPIC";
+const CURRENCY_PAIRS_ENV: &str = "FX_PLOT_CURRENCY_PAIRS";
+const FIELD_COUNT_ENV: &str = "FX_PLOT_FIELD_COUNT";
+const WINDOW_WIDTH_ENV: &str = "FX_PLOT_WINDOW_WIDTH";
+const WINDOW_HEIGHT_ENV: &str = "FX_PLOT_WINDOW_HEIGHT";
+const METRICS_ADDR_ENV: &str = "FX_PLOT_METRICS_ADDR";
+const PERSIST_ENABLED_ENV: &str = "FX_PLOT_PERSIST_ENABLED";
+const DATABASE_URL_ENV: &str = "FX_PLOT_DATABASE_URL";
+const BACKFILL_HOURS_ENV: &str = "FX_PLOT_BACKFILL_HOURS";
+
+/// Path to an optional `KEY=VALUE` file read before falling back to defaults.
+/// Real environment variables always take precedence over this file.
+const CONFIG_FILE_ENV: &str = "FX_PLOT_CONFIG_FILE";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Invalid { field: &'static str, reason: String },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid { field, reason } => write!(f, "invalid {field} - {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Debug, Clone)]
+pub struct KafkaConfig {
+    pub bootstrap_servers: String,
+    pub group_id: String,
+    pub auto_offset_reset: String,
+    pub socket_timeout_ms: String,
+    pub topic: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PersistenceConfig {
+    pub enabled: bool,
+    pub database_url: Option<String>,
+    pub backfill_hours: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub kafka: KafkaConfig,
+    pub currency_pairs: Vec<String>,
+    pub expected_field_count: usize,
+    pub window_width: f32,
+    pub window_height: f32,
+    pub metrics_addr: String,
+    pub persistence: PersistenceConfig,
+}
+
+impl Config {
+    /// Build the `Config` from environment variables, optionally seeded by a
+    /// `KEY=VALUE` file named in `FX_PLOT_CONFIG_FILE`. Real env vars always
+    /// win over the file, and everything else falls back to the same
+    /// defaults this viewer has always run with.
+    pub fn load() -> Result<Self, ConfigError> {
+        let file_values = read_config_file();
+
+        let kafka = KafkaConfig {
+            bootstrap_servers: lookup(&file_values, BOOTSTRAP_SERVERS_ENV, "localhost:9092"),
+            group_id: lookup(&file_values, GROUP_ID_ENV, "group1"),
+            auto_offset_reset: lookup(&file_values, AUTO_OFFSET_RESET_ENV, "latest"),
+            socket_timeout_ms: lookup(&file_values, SOCKET_TIMEOUT_MS_ENV, "6000"),
+            topic: lookup(&file_values, TOPIC_ENV, "fx-topic"),
+        };
+
+        let currency_pairs: Vec<String> = lookup(&file_values, CURRENCY_PAIRS_ENV, "EUR/USD")
+            .split(',')
+            .map(|pair| pair.trim().to_string())
+            .filter(|pair| !pair.is_empty())
+            .collect();
+        if currency_pairs.is_empty() {
+            return Err(ConfigError::Invalid {
+                field: CURRENCY_PAIRS_ENV,
+                reason: "must list at least one currency pair".to_string(),
+            });
+        }
+
+        let expected_field_count: usize = parse(&file_values, FIELD_COUNT_ENV, "9")?;
+        if expected_field_count == 0 {
+            return Err(ConfigError::Invalid {
+                field: FIELD_COUNT_ENV,
+                reason: "must be greater than zero".to_string(),
+            });
+        }
+
+        let window_width: f32 = parse(&file_values, WINDOW_WIDTH_ENV, "1024")?;
+        let window_height: f32 = parse(&file_values, WINDOW_HEIGHT_ENV, "768")?;
+        if window_width <= 0.0 || window_height <= 0.0 {
+            return Err(ConfigError::Invalid {
+                field: WINDOW_WIDTH_ENV,
+                reason: "window size must be positive".to_string(),
+            });
+        }
+
+        let metrics_addr = lookup(&file_values, METRICS_ADDR_ENV, "0.0.0.0:9898");
+
+        let persist_enabled_raw = lookup(&file_values, PERSIST_ENABLED_ENV, "false");
+        let enabled = persist_enabled_raw == "true" || persist_enabled_raw == "1";
+        let database_url = optional_lookup(&file_values, DATABASE_URL_ENV);
+        if enabled && database_url.is_none() {
+            return Err(ConfigError::Invalid {
+                field: DATABASE_URL_ENV,
+                reason: format!("{PERSIST_ENABLED_ENV} is set but no database URL was given"),
+            });
+        }
+        let backfill_hours: i64 = parse(&file_values, BACKFILL_HOURS_ENV, "24")?;
+        if backfill_hours < 0 {
+            return Err(ConfigError::Invalid {
+                field: BACKFILL_HOURS_ENV,
+                reason: "must not be negative".to_string(),
+            });
+        }
+
+        Ok(Self {
+            kafka,
+            currency_pairs,
+            expected_field_count,
+            window_width,
+            window_height,
+            metrics_addr,
+            persistence: PersistenceConfig {
+                enabled,
+                database_url,
+                backfill_hours,
+            },
+        })
+    }
+}
+
+fn read_config_file() -> HashMap<String, String> {
+    let Ok(path) = std::env::var(CONFIG_FILE_ENV) else {
+        return HashMap::new();
+    };
+
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn lookup(file_values: &HashMap<String, String>, env_name: &str, default: &str) -> String {
+    std::env::var(env_name)
+        .ok()
+        .or_else(|| file_values.get(env_name).cloned())
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn optional_lookup(file_values: &HashMap<String, String>, env_name: &str) -> Option<String> {
+    std::env::var(env_name).ok().or_else(|| file_values.get(env_name).cloned())
+}
+
+fn parse<T: std::str::FromStr>(
+    file_values: &HashMap<String, String>,
+    env_name: &'static str,
+    default: &str,
+) -> Result<T, ConfigError> {
+    lookup(file_values, env_name, default)
+        .parse()
+        .map_err(|_| ConfigError::Invalid {
+            field: env_name,
+            reason: "could not be parsed".to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // Config::load reads real process env vars, so tests that touch them must
+    // not run concurrently with each other.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    const ALL_ENV_VARS: [&str; 14] = [
+        BOOTSTRAP_SERVERS_ENV,
+        GROUP_ID_ENV,
+        AUTO_OFFSET_RESET_ENV,
+        SOCKET_TIMEOUT_MS_ENV,
+        TOPIC_ENV,
+        CURRENCY_PAIRS_ENV,
+        FIELD_COUNT_ENV,
+        WINDOW_WIDTH_ENV,
+        WINDOW_HEIGHT_ENV,
+        METRICS_ADDR_ENV,
+        PERSIST_ENABLED_ENV,
+        DATABASE_URL_ENV,
+        BACKFILL_HOURS_ENV,
+        CONFIG_FILE_ENV,
+    ];
+
+    fn clear_env() {
+        for key in ALL_ENV_VARS {
+            unsafe { std::env::remove_var(key) };
+        }
+    }
+
+    #[test]
+    fn load_defaults_are_valid() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        clear_env();
+        let config = Config::load().expect("defaults should be valid");
+        assert_eq!(config.currency_pairs, vec!["EUR/USD".to_string()]);
+        assert_eq!(config.persistence.backfill_hours, 24);
+    }
+
+    #[test]
+    fn rejects_empty_currency_pairs() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        clear_env();
+        unsafe { std::env::set_var(CURRENCY_PAIRS_ENV, " , ,") };
+        assert!(Config::load().is_err());
+        clear_env();
+    }
+
+    #[test]
+    fn rejects_zero_field_count() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        clear_env();
+        unsafe { std::env::set_var(FIELD_COUNT_ENV, "0") };
+        assert!(Config::load().is_err());
+        clear_env();
+    }
+
+    #[test]
+    fn rejects_non_positive_window_size() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        clear_env();
+        unsafe { std::env::set_var(WINDOW_WIDTH_ENV, "0") };
+        assert!(Config::load().is_err());
+        clear_env();
+    }
+
+    #[test]
+    fn rejects_persist_enabled_without_database_url() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        clear_env();
+        unsafe { std::env::set_var(PERSIST_ENABLED_ENV, "true") };
+        assert!(Config::load().is_err());
+        clear_env();
+    }
+
+    #[test]
+    fn rejects_negative_backfill_hours() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        clear_env();
+        unsafe { std::env::set_var(BACKFILL_HOURS_ENV, "-1") };
+        assert!(Config::load().is_err());
+        clear_env();
+    }
+}
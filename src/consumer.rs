@@ -11,32 +11,305 @@ use std::io;
 use std::num::ParseFloatError;
 use std::num::ParseIntError;
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, UNIX_EPOCH};
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
 #[derive(Debug, Default)]
 pub struct MarketData {
     pub liquidity_providers: Vec<LpBuyPoints>,
+    /// Full-fidelity tick history (all six volume/side prices), kept
+    /// alongside the per-LP plot points so a session can be exported later.
+    pub ticks: Vec<Tick>,
 }
 
 impl MarketData {
-    pub fn update(&mut self, market_data: &str) -> Result<(), AppError> {
-        extract_market_data(self, market_data)?;
-        Ok(())
+    pub fn update(
+        &mut self,
+        market_data: &str,
+        expected_field_count: usize,
+        currency_pairs: &[String],
+    ) -> Result<Tick, AppError> {
+        let tick = extract_market_data(self, market_data, expected_field_count, currency_pairs)?;
+        self.ticks.push(tick.clone());
+        Ok(tick)
     }
     pub fn new() -> Self {
         Self {
             liquidity_providers: Vec::new(),
+            ticks: Vec::new(),
         }
     }
 }
 
+/// A single parsed market data message, independent of any one LP's plot
+/// history - this is what gets handed to the persistence layer.
+#[derive(Debug, Clone)]
+pub struct Tick {
+    pub liquidity_provider: String,
+    pub currency_pair: String,
+    pub one_mill_buy_price: f64,
+    pub one_mill_sell_price: f64,
+    pub three_mill_buy_price: f64,
+    pub three_mill_sell_price: f64,
+    pub five_mill_buy_price: f64,
+    pub five_mill_sell_price: f64,
+    pub timestamp: u64,
+}
+
 #[derive(Debug, Default)]
 pub struct LpBuyPoints {
     pub name: String,
+    pub currency_pair: String,
     pub buy_points: Vec<[f64; 2]>,
     pub zero_time_ref: u64,
     pub global_start_hour: f64,
     pub global_start_minute: f64,
+    pub resolution: Resolution,
+    pub candles: Vec<Candle>,
+    pub ladder: Ladder,
+}
+
+/// Trade size tier parsed out of a market data message - `fx-topic` quotes
+/// buy/sell prices at each of these three sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeTier {
+    OneMillion,
+    ThreeMillion,
+    FiveMillion,
+}
+
+impl VolumeTier {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OneMillion => "1M",
+            Self::ThreeMillion => "3M",
+            Self::FiveMillion => "5M",
+        }
+    }
+
+    /// Size in millions, used as the x axis of the depth view.
+    pub fn size(&self) -> f64 {
+        match self {
+            Self::OneMillion => 1.0,
+            Self::ThreeMillion => 3.0,
+            Self::FiveMillion => 5.0,
+        }
+    }
+
+    pub const ALL: [VolumeTier; 3] = [Self::OneMillion, Self::ThreeMillion, Self::FiveMillion];
+}
+
+impl Default for VolumeTier {
+    fn default() -> Self {
+        Self::OneMillion
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Buy => "Buy",
+            Self::Sell => "Sell",
+        }
+    }
+
+    pub const ALL: [Side; 2] = [Self::Buy, Self::Sell];
+}
+
+impl Default for Side {
+    fn default() -> Self {
+        Self::Buy
+    }
+}
+
+/// All six buy/sell series across the three volume tiers, kept alongside
+/// `buy_points` so the UI can plot any tier/side the user selects, a
+/// bid/ask spread overlay, or a price-vs-size depth curve.
+#[derive(Debug, Default)]
+pub struct Ladder {
+    pub one_mill_buy: Vec<[f64; 2]>,
+    pub one_mill_sell: Vec<[f64; 2]>,
+    pub three_mill_buy: Vec<[f64; 2]>,
+    pub three_mill_sell: Vec<[f64; 2]>,
+    pub five_mill_buy: Vec<[f64; 2]>,
+    pub five_mill_sell: Vec<[f64; 2]>,
+}
+
+impl Ladder {
+    fn push(&mut self, adjusted_timestamp: f64, tick: &Tick) {
+        self.one_mill_buy.push([adjusted_timestamp, tick.one_mill_buy_price]);
+        self.one_mill_sell.push([adjusted_timestamp, tick.one_mill_sell_price]);
+        self.three_mill_buy.push([adjusted_timestamp, tick.three_mill_buy_price]);
+        self.three_mill_sell.push([adjusted_timestamp, tick.three_mill_sell_price]);
+        self.five_mill_buy.push([adjusted_timestamp, tick.five_mill_buy_price]);
+        self.five_mill_sell.push([adjusted_timestamp, tick.five_mill_sell_price]);
+    }
+
+    pub fn series(&self, tier: VolumeTier, side: Side) -> &Vec<[f64; 2]> {
+        match (tier, side) {
+            (VolumeTier::OneMillion, Side::Buy) => &self.one_mill_buy,
+            (VolumeTier::OneMillion, Side::Sell) => &self.one_mill_sell,
+            (VolumeTier::ThreeMillion, Side::Buy) => &self.three_mill_buy,
+            (VolumeTier::ThreeMillion, Side::Sell) => &self.three_mill_sell,
+            (VolumeTier::FiveMillion, Side::Buy) => &self.five_mill_buy,
+            (VolumeTier::FiveMillion, Side::Sell) => &self.five_mill_sell,
+        }
+    }
+
+    /// Bid/ask spread (sell - buy) at `tier` over time.
+    pub fn spread(&self, tier: VolumeTier) -> Vec<[f64; 2]> {
+        self.series(tier, Side::Buy)
+            .iter()
+            .zip(self.series(tier, Side::Sell).iter())
+            .map(|(buy, sell)| [buy[0], sell[1] - buy[1]])
+            .collect()
+    }
+
+    /// Price-vs-size curve at the latest timestamp, one point per tier, for
+    /// the given `side` - shows slippage across trade size.
+    pub fn depth(&self, side: Side) -> Vec<[f64; 2]> {
+        VolumeTier::ALL
+            .iter()
+            .filter_map(|tier| self.series(*tier, side).last().map(|point| [tier.size(), point[1]]))
+            .collect()
+    }
+}
+
+impl LpBuyPoints {
+    /// Fold a new (seconds-since-start, price) tick into the candle series for
+    /// this LP's current `resolution`, starting a fresh candle whenever the
+    /// tick's bucket moves past the in-progress one. If one or more buckets
+    /// pass with no tick landing in them, a flat candle (OHLC = the previous
+    /// candle's close, zero volume) is backfilled for each, so the series has
+    /// no gap.
+    fn update_candle(&mut self, adjusted_timestamp: f64, price: f64) {
+        let duration_secs = self.resolution.duration().as_secs_f64();
+        let bucket_start = (adjusted_timestamp / duration_secs).floor() * duration_secs;
+
+        match self.candles.last_mut() {
+            Some(candle) if candle.start_time == bucket_start => {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+                candle.volume += 1;
+            }
+            Some(prev) => {
+                // moving into a later bucket - close out the previous candle and
+                // seed the new one's open with its close so the series has no gap
+                prev.complete = true;
+                let open = prev.close;
+
+                let mut gap_start = prev.start_time + duration_secs;
+                while gap_start < bucket_start {
+                    self.candles.push(Candle {
+                        start_time: gap_start,
+                        end_time: gap_start + duration_secs,
+                        open,
+                        high: open,
+                        low: open,
+                        close: open,
+                        volume: 0,
+                        complete: true,
+                    });
+                    gap_start += duration_secs;
+                }
+
+                self.candles.push(Candle {
+                    start_time: bucket_start,
+                    end_time: bucket_start + duration_secs,
+                    open,
+                    high: open.max(price),
+                    low: open.min(price),
+                    close: price,
+                    volume: 1,
+                    complete: false,
+                });
+            }
+            None => {
+                self.candles.push(Candle {
+                    start_time: bucket_start,
+                    end_time: bucket_start + duration_secs,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: 1,
+                    complete: false,
+                });
+            }
+        }
+    }
+
+    /// Recompute the candle series from scratch at a new resolution, replaying
+    /// the full tick history kept in `buy_points`.
+    pub fn rebuild_candles(&mut self, resolution: Resolution) {
+        self.resolution = resolution;
+        self.candles.clear();
+        for [adjusted_timestamp, price] in self.buy_points.clone() {
+            self.update_candle(adjusted_timestamp, price);
+        }
+    }
+}
+
+/// Candle resolution offered in the viewer's aggregation controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+}
+
+impl Resolution {
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::OneMinute => Duration::from_secs(60),
+            Self::FiveMinutes => Duration::from_secs(5 * 60),
+            Self::FifteenMinutes => Duration::from_secs(15 * 60),
+            Self::OneHour => Duration::from_secs(60 * 60),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::OneMinute => "1m",
+            Self::FiveMinutes => "5m",
+            Self::FifteenMinutes => "15m",
+            Self::OneHour => "1h",
+        }
+    }
+
+    pub const ALL: [Resolution; 4] = [
+        Self::OneMinute,
+        Self::FiveMinutes,
+        Self::FifteenMinutes,
+        Self::OneHour,
+    ];
+}
+
+impl Default for Resolution {
+    fn default() -> Self {
+        Self::OneMinute
+    }
+}
+
+/// One OHLC bar for a given LP at a given `Resolution`. `start_time`/`end_time`
+/// are seconds-since-start, matching the x axis already used by `buy_points`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Candle {
+    pub start_time: f64,
+    pub end_time: f64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: u64,
+    pub complete: bool,
 }
 
 #[derive(Debug)]
@@ -44,9 +317,12 @@ pub struct LpBuyPoints {
 pub enum AppError {
     NumParams,
     IsEmpty,
+    UnsubscribedCurrencyPair(String),
     ParseFloat(ParseFloatError),
     ParseInt(ParseIntError),
     Io(io::Error),
+    Postgres(tokio_postgres::Error),
+    Persistence(String),
 }
 
 impl From<ParseFloatError> for AppError {
@@ -67,33 +343,52 @@ impl From<io::Error> for AppError {
     }
 }
 
+impl From<tokio_postgres::Error> for AppError {
+    fn from(error: tokio_postgres::Error) -> Self {
+        Self::Postgres(error)
+    }
+}
+
 impl Display for AppError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::IsEmpty => f.write_str("empty data field"),
             Self::NumParams => f.write_str("missing market data fields"),
+            Self::UnsubscribedCurrencyPair(pair) => {
+                write!(f, "currency pair {pair} is not in the configured currency_pairs")
+            }
             Self::ParseFloat(e) => Display::fmt(e, f),
             Self::ParseInt(e) => Display::fmt(e, f),
             Self::Io(e) => Display::fmt(e, f),
+            Self::Postgres(e) => Display::fmt(e, f),
+            Self::Persistence(msg) => f.write_str(msg),
         }
     }
 }
 
 impl std::error::Error for AppError {}
 
-pub async fn start(ctx: Context, market_data_mutex: Arc<Mutex<MarketData>>) {
-    let consumer: StreamConsumer = crate::consumer::create();
+pub async fn start(ctx: Context, market_data_mutex: Arc<Mutex<MarketData>>, config: crate::config::Config) {
+    let store = crate::persistence::backfill(
+        &market_data_mutex,
+        &config.persistence,
+        config.expected_field_count,
+        &config.currency_pairs,
+    )
+    .await
+    .map(Arc::new);
+    let consumer: StreamConsumer = crate::consumer::create(&config.kafka);
     info!("fx_plot kafka consumer started");
-    consume(consumer, ctx, market_data_mutex).await;
+    consume(consumer, ctx, market_data_mutex, store, config).await;
 }
 
-fn create() -> StreamConsumer {
+fn create(kafka: &crate::config::KafkaConfig) -> StreamConsumer {
     let mut binding = ClientConfig::new();
     let config = binding
-        .set("bootstrap.servers", "localhost:9092")
-        .set("group.id", "group1")
-        .set("auto.offset.reset", "latest")
-        .set("socket.timeout.ms", "6000");
+        .set("bootstrap.servers", &kafka.bootstrap_servers)
+        .set("group.id", &kafka.group_id)
+        .set("auto.offset.reset", &kafka.auto_offset_reset)
+        .set("socket.timeout.ms", &kafka.socket_timeout_ms);
 
     let consumer: StreamConsumer = config.create().expect("Consumer1 creation error"); // handle error properly later
     consumer
@@ -103,10 +398,12 @@ async fn consume(
     consumer: StreamConsumer,
     ctx: Context,
     market_data_mutex: Arc<Mutex<MarketData>>,
+    store: Option<Arc<crate::persistence::Store>>,
+    config: crate::config::Config,
 ) {
     info!("fx_plot kafka consumer: consuming messages...");
     consumer
-        .subscribe(&["fx-topic"])
+        .subscribe(&[config.kafka.topic.as_str()])
         .expect("Consumer1 can't subscribe to specified topic"); // handle error properly later
 
     loop {
@@ -116,19 +413,34 @@ async fn consume(
                 e
             ),
             Ok(m) => {
+                crate::metrics::record_message_received();
+                let processing_started = Instant::now();
                 match m.payload_view::<str>() {
                     None => info!("No payload"),
                     Some(Ok(s)) => {
                         info!("Received message: {}", s);
                         //update market data
                         let mut market_data = market_data_mutex.lock().unwrap(); // panic if can't get lock
-                        if let Err(e) = market_data.update(s) {
-                            error!("market data not processed - {e}");
-                        } else {
-                            // println!("consume updated market data: {:?}", *market_data);
-                            ctx.request_repaint();
-                            // thread::sleep(Duration::from_millis(1));
+                        match market_data.update(s, config.expected_field_count, &config.currency_pairs) {
+                            Err(e) => {
+                                crate::metrics::record_parse_failure(&e);
+                                error!("market data not processed - {e}");
+                            }
+                            Ok(tick) => {
+                                // println!("consume updated market data: {:?}", *market_data);
+                                crate::metrics::record_tick(&tick.liquidity_provider, tick.timestamp);
+                                ctx.request_repaint();
+                                // thread::sleep(Duration::from_millis(1));
+                                if let Some(store) = store.clone() {
+                                    tokio::spawn(async move {
+                                        if let Err(e) = store.insert_tick(&tick).await {
+                                            error!("tick not persisted - {e}");
+                                        }
+                                    });
+                                }
+                            }
                         }
+                        crate::metrics::record_processing_latency(processing_started);
                     }
                     Some(Err(e)) => error!(
                         "fx_plot kafka consumer: Error while deserializing message payload: {:?}",
@@ -141,38 +453,56 @@ async fn consume(
     }
 }
 
-fn extract_market_data(market_data: &mut MarketData, payload: &str) -> Result<(), AppError> {
-    let mut vol_prices_vec: Vec<(i32, f64, String)> = Vec::new();
-    let mut market_data_params = get_params(payload, 9)?;
+fn extract_market_data(
+    market_data: &mut MarketData,
+    payload: &str,
+    expected_field_count: usize,
+    currency_pairs: &[String],
+) -> Result<Tick, AppError> {
+    let mut market_data_params = get_params(payload, expected_field_count)?;
     let liquidity_provider = get_str_field(market_data_params.next())?;
-    let _currency_pair = get_str_field(market_data_params.next())?;
+    let currency_pair = get_str_field(market_data_params.next())?;
+    if !currency_pairs.iter().any(|pair| pair == currency_pair) {
+        return Err(AppError::UnsubscribedCurrencyPair(currency_pair.to_string()));
+    }
     let one_mill_buy_price: f64 = market_data_params.next().unwrap_or("").trim().parse()?;
-    vol_prices_vec.push((1, one_mill_buy_price, String::from("Buy")));
     let one_mill_sell_price: f64 = market_data_params.next().unwrap_or("").trim().parse()?;
-    vol_prices_vec.push((1, one_mill_sell_price, String::from("Sell")));
     let three_mill_buy_price: f64 = market_data_params.next().unwrap_or("").trim().parse()?;
-    vol_prices_vec.push((3, three_mill_buy_price, String::from("Buy")));
     let three_mill_sell_price: f64 = market_data_params.next().unwrap_or("").trim().parse()?;
-    vol_prices_vec.push((3, three_mill_sell_price, String::from("Sell")));
     let five_mill_buy_price: f64 = market_data_params.next().unwrap_or("").trim().parse()?;
-    vol_prices_vec.push((5, five_mill_buy_price, String::from("Buy")));
     let five_mill_sell_price: f64 = market_data_params.next().unwrap_or("").trim().parse()?;
-    vol_prices_vec.push((5, five_mill_sell_price, String::from("Sell")));
     let timestamp: u64 = market_data_params.next().unwrap_or("").trim().parse()?;
 
-    //build up liquidity_providers buy points vector
+    let tick = Tick {
+        liquidity_provider: liquidity_provider.to_string(),
+        currency_pair: currency_pair.to_string(),
+        one_mill_buy_price,
+        one_mill_sell_price,
+        three_mill_buy_price,
+        three_mill_sell_price,
+        five_mill_buy_price,
+        five_mill_sell_price,
+        timestamp,
+    };
+
+    //build up liquidity_providers buy points vector, keyed by (name, currency_pair)
+    // so that an LP quoting more than one pair doesn't interleave them into one series
     // how quick is this lookup? should we use a lookup table instead?
     if market_data
         .liquidity_providers
         .iter()
-        .all(|lp| lp.name != liquidity_provider)
+        .all(|lp| lp.name != liquidity_provider || lp.currency_pair != currency_pair)
     {
         let new_lp = LpBuyPoints {
             name: liquidity_provider.to_string(),
+            currency_pair: currency_pair.to_string(),
             buy_points: Vec::new(),
             zero_time_ref: 0,
             global_start_hour: 0.0,
             global_start_minute: 0.0,
+            resolution: Resolution::default(),
+            candles: Vec::new(),
+            ladder: Ladder::default(),
         };
         market_data.liquidity_providers.push(new_lp);
     }
@@ -181,12 +511,14 @@ fn extract_market_data(market_data: &mut MarketData, payload: &str) -> Result<()
     if let Some(lp) = market_data
         .liquidity_providers
         .iter_mut()
-        .find(|lp| lp.name == liquidity_provider)
+        .find(|lp| lp.name == liquidity_provider && lp.currency_pair == currency_pair)
     {
         // set first timestamp as zero time reference
         if lp.buy_points.len() == 0 {
             lp.zero_time_ref = timestamp;
             lp.buy_points.push([0.0, one_mill_buy_price]);
+            lp.update_candle(0.0, one_mill_buy_price);
+            lp.ladder.push(0.0, &tick);
             let d = UNIX_EPOCH + Duration::from_nanos(timestamp);
             let datetime = DateTime::<Utc>::from(d);
             let hour: f64 = match datetime.format("%H").to_string().parse::<f64>() {
@@ -211,10 +543,12 @@ fn extract_market_data(market_data: &mut MarketData, payload: &str) -> Result<()
             let adjusted_timestamp = (timestamp - lp.zero_time_ref) / 1000000000; // convert to seconds for egui plot x axis
             lp.buy_points
                 .push([adjusted_timestamp as f64, one_mill_buy_price]);
+            lp.update_candle(adjusted_timestamp as f64, one_mill_buy_price);
+            lp.ladder.push(adjusted_timestamp as f64, &tick);
         }
     }
 
-    Ok(())
+    Ok(tick)
 }
 
 pub fn get_params(data: &str, number: usize) -> Result<std::str::Split<'_, &str>, AppError> {
@@ -234,3 +568,146 @@ pub fn get_str_field(field: Option<&str>) -> Result<&str, AppError> {
         Ok(value.trim())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lp_at(resolution: Resolution) -> LpBuyPoints {
+        LpBuyPoints {
+            resolution,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn first_tick_opens_a_single_incomplete_candle() {
+        let mut lp = lp_at(Resolution::OneMinute);
+        lp.update_candle(10.0, 1.1);
+
+        assert_eq!(lp.candles.len(), 1);
+        let candle = lp.candles[0];
+        assert_eq!(candle.start_time, 0.0);
+        assert_eq!(candle.end_time, 60.0);
+        assert_eq!(candle.open, 1.1);
+        assert_eq!(candle.high, 1.1);
+        assert_eq!(candle.low, 1.1);
+        assert_eq!(candle.close, 1.1);
+        assert_eq!(candle.volume, 1);
+        assert!(!candle.complete);
+    }
+
+    #[test]
+    fn ticks_in_the_same_bucket_update_the_open_candle() {
+        let mut lp = lp_at(Resolution::OneMinute);
+        lp.update_candle(10.0, 1.1);
+        lp.update_candle(20.0, 1.3);
+        lp.update_candle(30.0, 1.0);
+
+        assert_eq!(lp.candles.len(), 1);
+        let candle = lp.candles[0];
+        assert_eq!(candle.open, 1.1);
+        assert_eq!(candle.high, 1.3);
+        assert_eq!(candle.low, 1.0);
+        assert_eq!(candle.close, 1.0);
+        assert_eq!(candle.volume, 3);
+        assert!(!candle.complete);
+    }
+
+    #[test]
+    fn a_tick_in_a_later_bucket_closes_the_previous_candle_and_carries_its_close_forward() {
+        let mut lp = lp_at(Resolution::OneMinute);
+        lp.update_candle(10.0, 1.1);
+        lp.update_candle(70.0, 1.4);
+
+        assert_eq!(lp.candles.len(), 2);
+
+        let first = lp.candles[0];
+        assert!(first.complete);
+        assert_eq!(first.close, 1.1);
+
+        let second = lp.candles[1];
+        assert!(!second.complete);
+        assert_eq!(second.start_time, 60.0);
+        assert_eq!(second.end_time, 120.0);
+        assert_eq!(second.open, 1.1); // seeded from the previous candle's close
+        assert_eq!(second.high, 1.4);
+        assert_eq!(second.low, 1.1);
+        assert_eq!(second.close, 1.4);
+        assert_eq!(second.volume, 1);
+    }
+
+    #[test]
+    fn a_tick_several_buckets_later_backfills_flat_candles_for_the_empty_buckets() {
+        let mut lp = lp_at(Resolution::OneMinute);
+        lp.update_candle(10.0, 1.1);
+        lp.update_candle(190.0, 1.4); // three buckets later (0, 60, 120, 180)
+
+        assert_eq!(lp.candles.len(), 4);
+
+        let first = lp.candles[0];
+        assert!(first.complete);
+        assert_eq!(first.start_time, 0.0);
+        assert_eq!(first.close, 1.1);
+
+        for gap in &lp.candles[1..3] {
+            assert!(gap.complete);
+            assert_eq!(gap.open, 1.1);
+            assert_eq!(gap.high, 1.1);
+            assert_eq!(gap.low, 1.1);
+            assert_eq!(gap.close, 1.1);
+            assert_eq!(gap.volume, 0);
+        }
+        assert_eq!(lp.candles[1].start_time, 60.0);
+        assert_eq!(lp.candles[2].start_time, 120.0);
+
+        let last = lp.candles[3];
+        assert!(!last.complete);
+        assert_eq!(last.start_time, 180.0);
+        assert_eq!(last.open, 1.1); // seeded from the last real candle's close
+        assert_eq!(last.high, 1.4);
+        assert_eq!(last.low, 1.1);
+        assert_eq!(last.close, 1.4);
+        assert_eq!(last.volume, 1);
+    }
+
+    #[test]
+    fn rebuild_candles_replays_buy_points_at_the_new_resolution() {
+        let mut lp = lp_at(Resolution::OneMinute);
+        lp.buy_points = vec![[10.0, 1.1], [70.0, 1.4], [650.0, 1.2]];
+
+        lp.rebuild_candles(Resolution::FiveMinutes);
+
+        assert_eq!(lp.resolution, Resolution::FiveMinutes);
+        assert_eq!(lp.candles.len(), 3);
+        assert_eq!(lp.candles[0].start_time, 0.0);
+        assert_eq!(lp.candles[1].start_time, 300.0);
+        assert_eq!(lp.candles[2].start_time, 600.0);
+    }
+
+    #[test]
+    fn update_rejects_a_tick_for_an_unsubscribed_currency_pair() {
+        let mut market_data = MarketData::new();
+        let currency_pairs = vec!["EUR/USD".to_string()];
+        let line = "LP1|GBP/USD|1.1|1.2|1.1|1.2|1.1|1.2|1000000000";
+
+        let result = market_data.update(line, 9, &currency_pairs);
+
+        assert!(matches!(result, Err(AppError::UnsubscribedCurrencyPair(pair)) if pair == "GBP/USD"));
+        assert!(market_data.liquidity_providers.is_empty());
+        assert!(market_data.ticks.is_empty());
+    }
+
+    #[test]
+    fn update_accepts_a_tick_for_a_subscribed_currency_pair() {
+        let mut market_data = MarketData::new();
+        let currency_pairs = vec!["EUR/USD".to_string()];
+        let line = "LP1|EUR/USD|1.1|1.2|1.1|1.2|1.1|1.2|1000000000";
+
+        let result = market_data.update(line, 9, &currency_pairs);
+
+        assert!(result.is_ok());
+        assert_eq!(market_data.liquidity_providers.len(), 1);
+        assert_eq!(market_data.ticks.len(), 1);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::consumer::{AppError, MarketData};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+const NANOS_PER_SECOND: f64 = 1_000_000_000.0;
+const NANOS_PER_HOUR: u64 = 3_600_000_000_000;
+
+/// Dump every captured tick to `path` as CSV, one row per tick: time in unix
+/// nanos, LP name, currency pair, the 1M/3M/5M buy and sell prices, and the
+/// adjusted seconds-since-start for that LP.
+pub fn export_ticks_csv(market_data: &MarketData, path: &Path) -> Result<(), AppError> {
+    let mut file = File::create(path)?;
+    writeln!(
+        file,
+        "time_unix_nanos,liquidity_provider,currency_pair,one_mill_buy,one_mill_sell,three_mill_buy,three_mill_sell,five_mill_buy,five_mill_sell,seconds_since_start"
+    )?;
+
+    for tick in &market_data.ticks {
+        let zero_time_ref = market_data
+            .liquidity_providers
+            .iter()
+            .find(|lp| lp.name == tick.liquidity_provider && lp.currency_pair == tick.currency_pair)
+            .map(|lp| lp.zero_time_ref)
+            .unwrap_or(tick.timestamp);
+        let seconds_since_start = tick.timestamp.saturating_sub(zero_time_ref) as f64 / NANOS_PER_SECOND;
+
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{},{},{}",
+            tick.timestamp,
+            tick.liquidity_provider,
+            tick.currency_pair,
+            tick.one_mill_buy_price,
+            tick.one_mill_sell_price,
+            tick.three_mill_buy_price,
+            tick.three_mill_sell_price,
+            tick.five_mill_buy_price,
+            tick.five_mill_sell_price,
+            seconds_since_start,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Hourly diagnostic summary per LP: tick count, ticks-per-second, and
+/// min/max/mean 1M buy price for that hour.
+pub fn export_hourly_summary_csv(market_data: &MarketData, path: &Path) -> Result<(), AppError> {
+    let mut file = File::create(path)?;
+    writeln!(
+        file,
+        "liquidity_provider,currency_pair,hour_start_unix_nanos,tick_count,ticks_per_second,min_price,max_price,mean_price"
+    )?;
+
+    // (liquidity_provider, currency_pair, hour bucket start) -> ticks in that hour
+    let mut buckets: BTreeMap<(String, String, u64), Vec<&crate::consumer::Tick>> = BTreeMap::new();
+    for tick in &market_data.ticks {
+        let hour_start = (tick.timestamp / NANOS_PER_HOUR) * NANOS_PER_HOUR;
+        buckets
+            .entry((tick.liquidity_provider.clone(), tick.currency_pair.clone(), hour_start))
+            .or_default()
+            .push(tick);
+    }
+
+    for ((liquidity_provider, currency_pair, hour_start), ticks) in buckets {
+        let n = ticks.len();
+        let first = ticks.iter().map(|t| t.timestamp).min().unwrap_or(hour_start);
+        let last = ticks.iter().map(|t| t.timestamp).max().unwrap_or(hour_start);
+        let span = Duration::from_nanos(last.saturating_sub(first));
+
+        let ticks_per_second = if span.as_secs_f64() < 1e-6 {
+            0.0
+        } else {
+            n as f64 / span.as_secs_f64()
+        };
+
+        let prices: Vec<f64> = ticks.iter().map(|t| t.one_mill_buy_price).collect();
+        let min_price = prices.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max_price = prices.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mean_price = prices.iter().sum::<f64>() / n as f64;
+
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{},{}",
+            liquidity_provider, currency_pair, hour_start, n, ticks_per_second, min_price, max_price, mean_price
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consumer::{MarketData, Tick};
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn tick(timestamp: u64) -> Tick {
+        tick_for_pair(timestamp, "EUR/USD")
+    }
+
+    fn tick_for_pair(timestamp: u64, currency_pair: &str) -> Tick {
+        Tick {
+            liquidity_provider: "LP1".to_string(),
+            currency_pair: currency_pair.to_string(),
+            one_mill_buy_price: 1.1,
+            one_mill_sell_price: 1.2,
+            three_mill_buy_price: 1.1,
+            three_mill_sell_price: 1.2,
+            five_mill_buy_price: 1.1,
+            five_mill_sell_price: 1.2,
+            timestamp,
+        }
+    }
+
+    fn scratch_path(name: &str) -> std::path::PathBuf {
+        let unique = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        std::env::temp_dir().join(format!("fx_plot_export_test_{name}_{unique}.csv"))
+    }
+
+    #[test]
+    fn zero_span_hour_reports_zero_ticks_per_second() {
+        let mut market_data = MarketData::new();
+        market_data.ticks.push(tick(NANOS_PER_HOUR));
+
+        let path = scratch_path("zero_span");
+        export_hourly_summary_csv(&market_data, &path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        let row = contents.lines().nth(1).expect("one data row");
+        let fields: Vec<&str> = row.split(',').collect();
+        assert_eq!(fields[3], "1"); // tick_count
+        assert_eq!(fields[4], "0"); // ticks_per_second
+    }
+
+    #[test]
+    fn multi_tick_hour_computes_a_positive_rate() {
+        let mut market_data = MarketData::new();
+        market_data.ticks.push(tick(NANOS_PER_HOUR));
+        market_data.ticks.push(tick(NANOS_PER_HOUR + NANOS_PER_SECOND as u64));
+        market_data.ticks.push(tick(NANOS_PER_HOUR + 2 * NANOS_PER_SECOND as u64));
+
+        let path = scratch_path("multi_tick");
+        export_hourly_summary_csv(&market_data, &path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        let row = contents.lines().nth(1).expect("one data row");
+        let fields: Vec<&str> = row.split(',').collect();
+        assert_eq!(fields[3], "3"); // tick_count
+        let ticks_per_second: f64 = fields[4].parse().unwrap();
+        assert!((ticks_per_second - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hourly_summary_keeps_currency_pairs_under_the_same_lp_separate() {
+        let mut market_data = MarketData::new();
+        market_data.ticks.push(tick_for_pair(NANOS_PER_HOUR, "EUR/USD"));
+        market_data.ticks.push(tick_for_pair(NANOS_PER_HOUR, "GBP/USD"));
+
+        let path = scratch_path("mixed_pairs");
+        export_hourly_summary_csv(&market_data, &path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        // one row per (liquidity_provider, currency_pair), not merged into one
+        assert_eq!(contents.lines().count(), 3); // header + 2 rows
+    }
+
+    #[test]
+    fn ticks_csv_uses_the_matching_pair_s_zero_time_ref() {
+        let mut market_data = MarketData::new();
+        market_data.liquidity_providers.push(crate::consumer::LpBuyPoints {
+            name: "LP1".to_string(),
+            currency_pair: "EUR/USD".to_string(),
+            zero_time_ref: NANOS_PER_HOUR,
+            ..Default::default()
+        });
+        market_data.liquidity_providers.push(crate::consumer::LpBuyPoints {
+            name: "LP1".to_string(),
+            currency_pair: "GBP/USD".to_string(),
+            zero_time_ref: NANOS_PER_HOUR + NANOS_PER_SECOND as u64,
+            ..Default::default()
+        });
+        market_data
+            .ticks
+            .push(tick_for_pair(NANOS_PER_HOUR + 2 * NANOS_PER_SECOND as u64, "GBP/USD"));
+
+        let path = scratch_path("zero_time_ref_by_pair");
+        export_ticks_csv(&market_data, &path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        let row = contents.lines().nth(1).expect("one data row");
+        let fields: Vec<&str> = row.split(',').collect();
+        let seconds_since_start: f64 = fields[9].parse().unwrap();
+        // should use GBP/USD's zero_time_ref (1s after the hour), not EUR/USD's
+        assert!((seconds_since_start - 1.0).abs() < 1e-9);
+    }
+}
@@ -1,13 +1,23 @@
+pub mod config;
 mod consumer;
+mod export;
+mod metrics;
+mod persistence;
 
+use config::Config;
+use consumer::{Resolution, Side, VolumeTier};
 use eframe::egui;
-use egui::Context;
-use egui_plot::{AxisHints, GridMark, Legend, Line, Plot, PlotPoints};
+use egui::{Color32, Context};
+use egui_plot::{
+    AxisHints, Bar, BarChart, BoxElem, BoxPlot, BoxSpread, GridMark, Legend, Line, Plot, PlotPoints,
+};
 use log::error;
 use std::ops::RangeInclusive;
+use std::path::PathBuf;
 use std::process::exit;
 use std::sync::{Arc, Mutex, mpsc};
 use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::runtime::Runtime;
 
 pub fn run<F: Future>(future: F) -> F::Output {
@@ -15,14 +25,21 @@ pub fn run<F: Future>(future: F) -> F::Output {
     rt.block_on(future)
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct FxViewerApp {
     pub market_data_mutex: Arc<Mutex<consumer::MarketData>>,
+    pub config: Config,
+    pub show_candles: bool,
+    pub resolution: Resolution,
+    pub volume_tier: VolumeTier,
+    pub side: Side,
+    pub show_spread: bool,
+    pub show_depth: bool,
 }
 
 impl FxViewerApp {
     /// Called once before the first frame.
-    pub fn init(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn init(cc: &eframe::CreationContext<'_>, config: Config) -> Self {
         let ctx = cc.egui_ctx.clone();
 
         let (ctx_tx, ctx_rx) = mpsc::channel();
@@ -33,11 +50,12 @@ impl FxViewerApp {
         let market_data_mutex = Arc::new(Mutex::new(market_data));
         let market_data_mutex_ui_clone = Arc::clone(&market_data_mutex);
         let market_data_mutex_fx_clone = Arc::clone(&market_data_mutex);
+        let fx_data_config = config.clone();
 
         thread::spawn(move || {
             // start fx data thread
             let rec_ctx: Context = ctx_rx.recv().unwrap();
-            run_async_fx_data(rec_ctx, market_data_mutex_fx_clone);
+            run_async_fx_data(rec_ctx, market_data_mutex_fx_clone, fx_data_config);
         }); // end of fx data thread
 
         if let Err(e) = ctx_tx.send(ctx) {
@@ -47,19 +65,31 @@ impl FxViewerApp {
 
         Self {
             market_data_mutex: market_data_mutex_ui_clone,
+            config,
+            show_candles: false,
+            resolution: Resolution::default(),
+            volume_tier: VolumeTier::default(),
+            side: Side::default(),
+            show_spread: false,
+            show_depth: false,
         }
     }
 }
 
-pub fn run_async_fx_data(rec_ctx: Context, market_data_mutex: Arc<Mutex<consumer::MarketData>>) {
+pub fn run_async_fx_data(
+    rec_ctx: Context,
+    market_data_mutex: Arc<Mutex<consumer::MarketData>>,
+    config: Config,
+) {
     run(async {
-        consumer::start(rec_ctx, market_data_mutex).await;
+        tokio::spawn(metrics::serve(config.metrics_addr.clone()));
+        consumer::start(rec_ctx, market_data_mutex, config).await;
     });
 }
 
 impl eframe::App for FxViewerApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let market_data = self.market_data_mutex.lock().unwrap(); // panic if can't get lock
+        let mut market_data = self.market_data_mutex.lock().unwrap(); // panic if can't get lock
 
         // if no market data yet, skip plotting
         if market_data.liquidity_providers.len() == 0 {
@@ -67,16 +97,148 @@ impl eframe::App for FxViewerApp {
             return;
         }
 
+        egui::TopBottomPanel::top("candle_controls").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.show_candles, "Show candles");
+                ui.add_enabled_ui(self.show_candles, |ui| {
+                    egui::ComboBox::from_label("Resolution")
+                        .selected_text(self.resolution.label())
+                        .show_ui(ui, |ui| {
+                            for resolution in Resolution::ALL {
+                                ui.selectable_value(&mut self.resolution, resolution, resolution.label());
+                            }
+                        });
+                });
+                ui.separator();
+                ui.add_enabled_ui(!self.show_candles, |ui| {
+                    egui::ComboBox::from_label("Volume tier")
+                        .selected_text(self.volume_tier.label())
+                        .show_ui(ui, |ui| {
+                            for tier in VolumeTier::ALL {
+                                ui.selectable_value(&mut self.volume_tier, tier, tier.label());
+                            }
+                        });
+                    egui::ComboBox::from_label("Side")
+                        .selected_text(self.side.label())
+                        .show_ui(ui, |ui| {
+                            for side in Side::ALL {
+                                ui.selectable_value(&mut self.side, side, side.label());
+                            }
+                        });
+                    ui.checkbox(&mut self.show_spread, "Show spread overlay");
+                    ui.checkbox(&mut self.show_depth, "Show depth view");
+                });
+                ui.separator();
+                if ui.button("Export CSV").clicked() {
+                    let path = export_path("fx_plot_export", "csv");
+                    if let Err(e) = export::export_ticks_csv(&market_data, &path) {
+                        error!("CSV export failed - {e}");
+                    }
+                }
+                if ui.button("Export hourly summary CSV").clicked() {
+                    let path = export_path("fx_plot_hourly_summary", "csv");
+                    if let Err(e) = export::export_hourly_summary_csv(&market_data, &path) {
+                        error!("hourly summary CSV export failed - {e}");
+                    }
+                }
+            });
+        });
+
+        if self.show_candles {
+            for lp in market_data.liquidity_providers.iter_mut() {
+                if lp.resolution != self.resolution {
+                    lp.rebuild_candles(self.resolution);
+                }
+            }
+        }
+
         let mut lines: Vec<Line> = Vec::new();
+        let mut box_plots: Vec<BoxPlot> = Vec::new();
+        let mut volume_charts: Vec<BarChart> = Vec::new();
 
         for i in 0..market_data.liquidity_providers.len() {
-            let plotpoints =
-                PlotPoints::from(market_data.liquidity_providers[i].buy_points.clone());
-            let line = Line::new(market_data.liquidity_providers[i].name.clone(), plotpoints);
+            if self.show_candles {
+                let lp = &market_data.liquidity_providers[i];
+                let bar_width = lp.resolution.duration().as_secs_f64() * 0.8;
+                let elems: Vec<BoxElem> = lp
+                    .candles
+                    .iter()
+                    .map(|candle| {
+                        // in-progress (not yet complete) candles are drawn half
+                        // as opaque, so the live bar reads as "still forming"
+                        let alpha = if candle.complete { 255 } else { 130 };
+                        let color = if candle.close >= candle.open {
+                            Color32::from_rgba_unmultiplied(0, 170, 0, alpha)
+                        } else {
+                            Color32::from_rgba_unmultiplied(200, 0, 0, alpha)
+                        };
+                        let body_low = candle.open.min(candle.close);
+                        let body_high = candle.open.max(candle.close);
+                        BoxElem::new(
+                            candle.start_time,
+                            BoxSpread::new(
+                                candle.low,
+                                body_low,
+                                (candle.open + candle.close) / 2.0,
+                                body_high,
+                                candle.high,
+                            ),
+                        )
+                        .fill(color)
+                        .stroke(egui::Stroke::new(1.0, color))
+                        .whisker_width(0.0)
+                        .box_width(bar_width)
+                    })
+                    .collect();
+
+                box_plots.push(BoxPlot::new(lp.name.clone(), elems));
+
+                let bars: Vec<Bar> = lp
+                    .candles
+                    .iter()
+                    .map(|candle| {
+                        let alpha = if candle.complete { 255 } else { 130 };
+                        let color = if candle.close >= candle.open {
+                            Color32::from_rgba_unmultiplied(0, 170, 0, alpha)
+                        } else {
+                            Color32::from_rgba_unmultiplied(200, 0, 0, alpha)
+                        };
+                        Bar::new(candle.start_time, candle.volume as f64)
+                            .width(bar_width)
+                            .fill(color)
+                            .stroke(egui::Stroke::new(1.0, color))
+                    })
+                    .collect();
+
+                volume_charts.push(BarChart::new(lp.name.clone(), bars));
+            } else {
+                let lp = &market_data.liquidity_providers[i];
+                let series = lp.ladder.series(self.volume_tier, self.side).clone();
+                let plotpoints = PlotPoints::from(series);
+                let label = format!("{} {} {}", lp.name, self.volume_tier.label(), self.side.label());
+                lines.push(Line::new(label, plotpoints));
 
-            lines.push(line);
+                if self.show_spread {
+                    let spread_points = PlotPoints::from(lp.ladder.spread(self.volume_tier));
+                    let spread_label = format!("{} {} spread", lp.name, self.volume_tier.label());
+                    lines.push(Line::new(spread_label, spread_points));
+                }
+            }
         }
 
+        let depth_lines: Vec<Line> = if self.show_depth && !self.show_candles {
+            market_data
+                .liquidity_providers
+                .iter()
+                .map(|lp| {
+                    let depth_points = PlotPoints::from(lp.ladder.depth(self.side));
+                    Line::new(format!("{} {}", lp.name, self.side.label()), depth_points)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         let time_formatter = |mark: GridMark, _range: &RangeInclusive<f64>| {
             let seconds = mark.value;
             let start_minute = market_data.liquidity_providers[0].global_start_minute as f64;
@@ -91,21 +253,67 @@ impl eframe::App for FxViewerApp {
             AxisHints::new_x().label("Time (seconds since start)"),
         ];
 
+        let currency_pairs = self.config.currency_pairs.join(", ");
+
         egui::CentralPanel::default().show(ctx, |ui| {
+            if !depth_lines.is_empty() {
+                Plot::new("fx_depth_plot")
+                    .x_axis_label("Size (millions)")
+                    .y_axis_label(format!("{currency_pairs} {} Price", self.side.label()))
+                    .legend(Legend::default().title(format!("{currency_pairs}\nDepth by Size")))
+                    .height(ui.available_height() / 2.0)
+                    .show(ui, |plot_ui| {
+                        for line in depth_lines.into_iter() {
+                            plot_ui.line(line);
+                        }
+                    });
+                ui.separator();
+            }
+
+            let candle_height = if self.show_candles && !volume_charts.is_empty() {
+                ui.available_height() * 0.7
+            } else {
+                ui.available_height()
+            };
+
             Plot::new("fx_plot")
                 .custom_x_axes(x_axes)
-                .y_axis_label("EUR/USD 1M Buy Price")
-                .legend(Legend::default().title("EUR/USD\nLiquidity Providers"))
+                .y_axis_label(format!("{currency_pairs} Price"))
+                .legend(Legend::default().title(format!("{currency_pairs}\nLiquidity Providers")))
+                .height(candle_height)
                 //  .time_formatter(time_formatter)
                 .show(ui, |plot_ui| {
                     for line in lines.into_iter() {
                         plot_ui.line(line);
                     }
+                    for box_plot in box_plots.into_iter() {
+                        plot_ui.box_plot(box_plot);
+                    }
                 });
+
+            if self.show_candles && !volume_charts.is_empty() {
+                ui.separator();
+                Plot::new("fx_volume_plot")
+                    .y_axis_label("Volume (ticks)")
+                    .legend(Legend::default().title("Volume"))
+                    .show(ui, |plot_ui| {
+                        for chart in volume_charts.into_iter() {
+                            plot_ui.bar_chart(chart);
+                        }
+                    });
+            }
         });
     }
 }
 
+fn export_path(prefix: &str, extension: &str) -> PathBuf {
+    let unix_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    PathBuf::from(format!("{prefix}_{unix_secs}.{extension}"))
+}
+
 fn get_time_axis_string(seconds: f64, start_hour: f64, start_minute: f64) -> String {
     const SECONDS_PER_MINUTE: f64 = 60.0;
     const MINUTES_PER_HOUR: f64 = 60.0;
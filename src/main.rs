@@ -1,3 +1,4 @@
+use fx_plot::config::Config;
 use log::error;
 use std::process::exit;
 
@@ -8,16 +9,24 @@ fn main() {
         exit(1);
     }
 
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("invalid configuration - {e}");
+            exit(1);
+        }
+    };
+
     let win_option = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1024.0, 768.0])
+            .with_inner_size([config.window_width, config.window_height])
             .with_min_inner_size([300.0, 220.0]),
         ..Default::default()
     };
     if let Err(e) = eframe::run_native(
         "Plot demo",
         win_option,
-        Box::new(|cc| Ok(Box::new(fx_plot::FxViewerApp::init(cc)))),
+        Box::new(|cc| Ok(Box::new(fx_plot::FxViewerApp::init(cc, config)))),
     ) {
         error!("error starting eframe - {e}");
         exit(1);
@@ -0,0 +1,128 @@
+use crate::consumer::AppError;
+use actix_web::{App, HttpResponse, HttpServer, get};
+use log::{error, info};
+use once_cell::sync::Lazy;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use std::time::Instant;
+
+pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+pub static MESSAGES_RECEIVED: Lazy<IntCounter> = Lazy::new(|| {
+    let counter = IntCounter::new(
+        "fx_plot_messages_received_total",
+        "Kafka messages received by the consumer",
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(counter.clone())).unwrap();
+    counter
+});
+
+pub static PARSE_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new(
+            "fx_plot_parse_failures_total",
+            "Market data messages that failed to parse, by AppError variant",
+        ),
+        &["variant"],
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(counter.clone())).unwrap();
+    counter
+});
+
+pub static TICKS_PER_LP: Lazy<IntCounterVec> = Lazy::new(|| {
+    let counter = IntCounterVec::new(
+        Opts::new("fx_plot_lp_ticks_total", "Ticks processed, by liquidity provider"),
+        &["liquidity_provider"],
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(counter.clone())).unwrap();
+    counter
+});
+
+pub static LAST_PROCESSED_TIMESTAMP: Lazy<IntGauge> = Lazy::new(|| {
+    let gauge = IntGauge::new(
+        "fx_plot_last_processed_timestamp_ns",
+        "Unix timestamp (ns) of the most recently processed tick",
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(gauge.clone())).unwrap();
+    gauge
+});
+
+pub static PROCESSING_LATENCY: Lazy<Histogram> = Lazy::new(|| {
+    let histogram = Histogram::with_opts(HistogramOpts::new(
+        "fx_plot_processing_latency_seconds",
+        "Time spent turning a raw Kafka payload into an updated market data point",
+    ))
+    .unwrap();
+    REGISTRY.register(Box::new(histogram.clone())).unwrap();
+    histogram
+});
+
+/// Variant name used as the `variant` label on `fx_plot_parse_failures_total`.
+fn error_variant(error: &AppError) -> &'static str {
+    match error {
+        AppError::NumParams => "num_params",
+        AppError::IsEmpty => "is_empty",
+        AppError::UnsubscribedCurrencyPair(_) => "unsubscribed_currency_pair",
+        AppError::ParseFloat(_) => "parse_float",
+        AppError::ParseInt(_) => "parse_int",
+        AppError::Io(_) => "io",
+        AppError::Postgres(_) => "postgres",
+        AppError::Persistence(_) => "persistence",
+    }
+}
+
+pub fn record_message_received() {
+    MESSAGES_RECEIVED.inc();
+}
+
+pub fn record_parse_failure(error: &AppError) {
+    PARSE_FAILURES.with_label_values(&[error_variant(error)]).inc();
+}
+
+pub fn record_tick(liquidity_provider: &str, timestamp: u64) {
+    TICKS_PER_LP.with_label_values(&[liquidity_provider]).inc();
+    LAST_PROCESSED_TIMESTAMP.set(timestamp as i64);
+}
+
+/// Measure the wall-clock time it took to process a message, from `started`
+/// to now, and record it against the processing latency histogram.
+pub fn record_processing_latency(started: Instant) {
+    PROCESSING_LATENCY.observe(started.elapsed().as_secs_f64());
+}
+
+#[get("/metrics")]
+async fn serve_metrics() -> HttpResponse {
+    let encoder = TextEncoder::new();
+    let metric_families = REGISTRY.gather();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&metric_families, &mut buffer) {
+        error!("error encoding prometheus metrics - {e}");
+        return HttpResponse::InternalServerError().finish();
+    }
+    HttpResponse::Ok()
+        .content_type(encoder.format_type())
+        .body(buffer)
+}
+
+/// Spawn the `/metrics` HTTP endpoint on its own actix-web server, as a
+/// background task alongside the Kafka consumer. Runs until the server
+/// stops; if `addr` cannot be bound it logs the error and returns.
+pub async fn serve(addr: String) {
+    info!("fx_plot metrics endpoint listening on {addr}");
+    let server = HttpServer::new(|| App::new().service(serve_metrics)).bind(&addr);
+
+    match server {
+        Ok(server) => {
+            if let Err(e) = server.run().await {
+                error!("metrics endpoint stopped - {e}");
+            }
+        }
+        Err(e) => error!("could not bind metrics endpoint on {addr} - {e}"),
+    }
+}
@@ -0,0 +1,220 @@
+use crate::config::PersistenceConfig;
+use crate::consumer::{AppError, MarketData, Tick};
+use deadpool_postgres::{Config as PoolConfig, Pool, Runtime as PoolRuntime};
+use log::{error, info};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+use tokio_postgres::NoTls;
+
+pub struct Store {
+    pool: Pool,
+}
+
+impl Store {
+    pub async fn connect(database_url: &str) -> Result<Self, AppError> {
+        let mut pool_config = PoolConfig::new();
+        pool_config.url = Some(database_url.to_string());
+        let pool = pool_config
+            .create_pool(Some(PoolRuntime::Tokio1), NoTls)
+            .map_err(|e| AppError::Persistence(e.to_string()))?;
+
+        let store = Self { pool };
+        store.ensure_schema().await?;
+        Ok(store)
+    }
+
+    async fn ensure_schema(&self) -> Result<(), AppError> {
+        let client = self.pool.get().await.map_err(|e| AppError::Persistence(e.to_string()))?;
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS fx_ticks (
+                    liquidity_provider TEXT NOT NULL,
+                    currency_pair TEXT NOT NULL,
+                    one_mill_buy_price DOUBLE PRECISION NOT NULL,
+                    one_mill_sell_price DOUBLE PRECISION NOT NULL,
+                    three_mill_buy_price DOUBLE PRECISION NOT NULL,
+                    three_mill_sell_price DOUBLE PRECISION NOT NULL,
+                    five_mill_buy_price DOUBLE PRECISION NOT NULL,
+                    five_mill_sell_price DOUBLE PRECISION NOT NULL,
+                    timestamp_ns BIGINT NOT NULL,
+                    PRIMARY KEY (liquidity_provider, currency_pair, timestamp_ns)
+                )",
+            )
+            .await
+            .map_err(AppError::Postgres)?;
+        Ok(())
+    }
+
+    pub async fn insert_tick(&self, tick: &Tick) -> Result<(), AppError> {
+        let client = self.pool.get().await.map_err(|e| AppError::Persistence(e.to_string()))?;
+        client
+            .execute(
+                "INSERT INTO fx_ticks (
+                    liquidity_provider, currency_pair,
+                    one_mill_buy_price, one_mill_sell_price,
+                    three_mill_buy_price, three_mill_sell_price,
+                    five_mill_buy_price, five_mill_sell_price,
+                    timestamp_ns
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (liquidity_provider, currency_pair, timestamp_ns) DO NOTHING",
+                &[
+                    &tick.liquidity_provider,
+                    &tick.currency_pair,
+                    &tick.one_mill_buy_price,
+                    &tick.one_mill_sell_price,
+                    &tick.three_mill_buy_price,
+                    &tick.three_mill_sell_price,
+                    &tick.five_mill_buy_price,
+                    &tick.five_mill_sell_price,
+                    &(tick.timestamp as i64),
+                ],
+            )
+            .await
+            .map_err(AppError::Postgres)?;
+        Ok(())
+    }
+
+    /// Earliest tick on record for `(liquidity_provider, currency_pair)`, if any.
+    pub async fn fetch_earliest(
+        &self,
+        liquidity_provider: &str,
+        currency_pair: &str,
+    ) -> Result<Option<Tick>, AppError> {
+        let client = self.pool.get().await.map_err(|e| AppError::Persistence(e.to_string()))?;
+        let row = client
+            .query_opt(
+                "SELECT liquidity_provider, currency_pair,
+                    one_mill_buy_price, one_mill_sell_price,
+                    three_mill_buy_price, three_mill_sell_price,
+                    five_mill_buy_price, five_mill_sell_price,
+                    timestamp_ns
+                FROM fx_ticks
+                WHERE liquidity_provider = $1 AND currency_pair = $2
+                ORDER BY timestamp_ns ASC
+                LIMIT 1",
+                &[&liquidity_provider, &currency_pair],
+            )
+            .await
+            .map_err(AppError::Postgres)?;
+        Ok(row.map(row_to_tick))
+    }
+
+    pub async fn fetch_from(&self, start_ns: i64, end_ns: i64) -> Result<Vec<Tick>, AppError> {
+        let client = self.pool.get().await.map_err(|e| AppError::Persistence(e.to_string()))?;
+        let rows = client
+            .query(
+                "SELECT liquidity_provider, currency_pair,
+                    one_mill_buy_price, one_mill_sell_price,
+                    three_mill_buy_price, three_mill_sell_price,
+                    five_mill_buy_price, five_mill_sell_price,
+                    timestamp_ns
+                FROM fx_ticks
+                WHERE timestamp_ns BETWEEN $1 AND $2
+                ORDER BY timestamp_ns ASC",
+                &[&start_ns, &end_ns],
+            )
+            .await
+            .map_err(AppError::Postgres)?;
+        Ok(rows.into_iter().map(row_to_tick).collect())
+    }
+}
+
+fn row_to_tick(row: tokio_postgres::Row) -> Tick {
+    Tick {
+        liquidity_provider: row.get(0),
+        currency_pair: row.get(1),
+        one_mill_buy_price: row.get(2),
+        one_mill_sell_price: row.get(3),
+        three_mill_buy_price: row.get(4),
+        three_mill_sell_price: row.get(5),
+        five_mill_buy_price: row.get(6),
+        five_mill_sell_price: row.get(7),
+        timestamp: row.get::<_, i64>(8) as u64,
+    }
+}
+
+/// Connect (if `persistence.enabled`) and pre-populate `market_data` with the
+/// last `persistence.backfill_hours` hours of ticks, so the plot isn't empty
+/// while the live Kafka stream is still warming up. Runs on the fx data
+/// thread before the Kafka consumer is created, and is a no-op when
+/// persistence isn't configured.
+pub async fn backfill(
+    market_data_mutex: &Arc<Mutex<MarketData>>,
+    persistence: &PersistenceConfig,
+    expected_field_count: usize,
+    currency_pairs: &[String],
+) -> Option<Store> {
+    if !persistence.enabled {
+        return None;
+    }
+
+    let Some(database_url) = persistence.database_url.as_deref() else {
+        error!("persistence enabled but no database URL configured - persistence disabled for this run");
+        return None;
+    };
+
+    let store = match Store::connect(database_url).await {
+        Ok(store) => store,
+        Err(e) => {
+            error!("could not connect to persistence store - {e}");
+            return None;
+        }
+    };
+
+    let now_ns = match UNIX_EPOCH.elapsed() {
+        Ok(d) => d.as_nanos() as i64,
+        Err(e) => {
+            error!("system clock before unix epoch - {e}");
+            return Some(store);
+        }
+    };
+    let start_ns =
+        now_ns - Duration::from_secs((persistence.backfill_hours * 3600) as u64).as_nanos() as i64;
+
+    let mut liquidity_providers: Vec<(String, String)> = Vec::new();
+    match store.fetch_from(start_ns, now_ns).await {
+        Ok(ticks) => {
+            info!("backfilling {} ticks from persistence store", ticks.len());
+            let mut market_data = market_data_mutex.lock().unwrap();
+            for tick in ticks {
+                let key = (tick.liquidity_provider.clone(), tick.currency_pair.clone());
+                if !liquidity_providers.contains(&key) {
+                    liquidity_providers.push(key);
+                }
+                let line = format!(
+                    "{}|{}|{}|{}|{}|{}|{}|{}|{}",
+                    tick.liquidity_provider,
+                    tick.currency_pair,
+                    tick.one_mill_buy_price,
+                    tick.one_mill_sell_price,
+                    tick.three_mill_buy_price,
+                    tick.three_mill_sell_price,
+                    tick.five_mill_buy_price,
+                    tick.five_mill_sell_price,
+                    tick.timestamp,
+                );
+                if let Err(e) = market_data.update(&line, expected_field_count, currency_pairs) {
+                    error!("backfilled tick not processed - {e}");
+                }
+            }
+        }
+        Err(e) => error!("backfill query failed - {e}"),
+    }
+
+    // log how far back each LP/pair's history actually goes, so an operator can
+    // tell a short backfill window apart from a pair that simply has no older data
+    for (liquidity_provider, currency_pair) in &liquidity_providers {
+        match store.fetch_earliest(liquidity_provider, currency_pair).await {
+            Ok(Some(earliest)) => {
+                info!(
+                    "{liquidity_provider} {currency_pair} earliest tick on record at timestamp {}",
+                    earliest.timestamp
+                )
+            }
+            Ok(None) => info!("{liquidity_provider} {currency_pair} has no ticks on record"),
+            Err(e) => error!("could not fetch earliest tick for {liquidity_provider} {currency_pair} - {e}"),
+        }
+    }
+
+    Some(store)
+}